@@ -0,0 +1,21 @@
+//! A small cache-line padding wrapper shared by the crate's lock-free data structures.
+//!
+
+/// Pads a value out to a cache line so that two independently-hot fields (e.g. a producer's and
+/// a consumer's indices) don't false-share.
+#[repr(align(64))]
+pub struct CachePadded<T>(pub T);
+
+impl<T> CachePadded<T> {
+    pub const fn new(value: T) -> Self {
+        CachePadded(value)
+    }
+}
+
+impl<T> std::ops::Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
@@ -14,36 +14,56 @@
 
 extern crate alloc;
 
-use std::iter::{Chain, Rev};
-use std::mem::replace;
+use std::iter::{Chain, Map, Rev};
+use std::mem::{replace, MaybeUninit};
 use std::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+use std::vec::Vec;
 
 use generic_array::{ArrayLength, GenericArray};
 
 /// A circular buffer-like queue.
-#[derive(Clone, Debug)]
-pub struct CircularQueue<T: Default + Sized + PartialEq, N: ArrayLength> {
-    data: GenericArray<T, N>,
+pub struct CircularQueue<T, N: ArrayLength> {
+    data: GenericArray<MaybeUninit<T>, N>,
     length: usize,
     insertion_index: usize,
 }
 
+fn uninit_ref<T>(slot: &MaybeUninit<T>) -> &T {
+    unsafe { slot.assume_init_ref() }
+}
+
+fn uninit_mut<T>(slot: &mut MaybeUninit<T>) -> &mut T {
+    unsafe { slot.assume_init_mut() }
+}
+
 /// An iterator over `CircularQueue<T>`.
-pub type Iter<'a, T> = Chain<Rev<SliceIter<'a, T>>, Rev<SliceIter<'a, T>>>;
+pub type Iter<'a, T> = Map<
+    Chain<Rev<SliceIter<'a, MaybeUninit<T>>>, Rev<SliceIter<'a, MaybeUninit<T>>>>,
+    fn(&'a MaybeUninit<T>) -> &'a T,
+>;
 
 /// A mutable iterator over `CircularQueue<T>`.
-pub type IterMut<'a, T> = Chain<Rev<SliceIterMut<'a, T>>, Rev<SliceIterMut<'a, T>>>;
+pub type IterMut<'a, T> = Map<
+    Chain<Rev<SliceIterMut<'a, MaybeUninit<T>>>, Rev<SliceIterMut<'a, MaybeUninit<T>>>>,
+    fn(&'a mut MaybeUninit<T>) -> &'a mut T,
+>;
 
 /// An ascending iterator over `CircularQueue<T>`.
-pub type AscIter<'a, T> = Chain<SliceIter<'a, T>, SliceIter<'a, T>>;
+pub type AscIter<'a, T> = Map<
+    Chain<SliceIter<'a, MaybeUninit<T>>, SliceIter<'a, MaybeUninit<T>>>,
+    fn(&'a MaybeUninit<T>) -> &'a T,
+>;
 
 /// An mutable ascending iterator over `CircularQueue<T>`.
-pub type AscIterMut<'a, T> = Chain<SliceIterMut<'a, T>, SliceIterMut<'a, T>>;
+pub type AscIterMut<'a, T> = Map<
+    Chain<SliceIterMut<'a, MaybeUninit<T>>, SliceIterMut<'a, MaybeUninit<T>>>,
+    fn(&'a mut MaybeUninit<T>) -> &'a mut T,
+>;
 
 /// A value popped from `CircularQueue<T>` as the result of a push operation.
 pub type Popped<T> = Option<T>;
 
-impl<T: Default + Sized + PartialEq, N: ArrayLength> PartialEq for CircularQueue<T, N> {
+impl<T: PartialEq, N: ArrayLength> PartialEq for CircularQueue<T, N> {
     fn eq(&self, other: &Self) -> bool {
         if self.len() != other.len() {
             return false;
@@ -59,9 +79,40 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> PartialEq for CircularQueue
     }
 }
 
-impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
+impl<T: Clone, N: ArrayLength> Clone for CircularQueue<T, N> {
+    fn clone(&self) -> Self {
+        let mut new_queue = CircularQueue::new();
+        for item in self.asc_iter() {
+            new_queue.push(item.clone());
+        }
+        new_queue
+    }
+}
+
+impl<T: std::fmt::Debug, N: ArrayLength> std::fmt::Debug for CircularQueue<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, N: ArrayLength> Drop for CircularQueue<T, N> {
+    fn drop(&mut self) {
+        if self.length == 0 {
+            return;
+        }
+        let (a, b) = self.data[0..self.length].split_at_mut(self.insertion_index);
+        for slot in a.iter_mut().chain(b.iter_mut()) {
+            unsafe {
+                slot.assume_init_drop();
+            }
+        }
+    }
+}
+
+impl<T, N: ArrayLength> CircularQueue<T, N> {
     pub fn new() -> Self {
-        let data: GenericArray<T, N> = GenericArray::default();
+        let data = GenericArray::try_from_iter((0..N::to_usize()).map(|_| MaybeUninit::uninit()))
+            .expect("GenericArray length mismatch");
         CircularQueue {
             data,
             length: 0,
@@ -101,6 +152,14 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
     ///
     #[inline]
     pub fn clear(&mut self) {
+        if self.length != 0 {
+            let (a, b) = self.data[0..self.length].split_at_mut(self.insertion_index);
+            for slot in a.iter_mut().chain(b.iter_mut()) {
+                unsafe {
+                    slot.assume_init_drop();
+                }
+            }
+        }
         self.insertion_index = 0;
         self.length = 0;
     }
@@ -120,10 +179,11 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
         }
 
         if !self.is_full() {
-            self.data[self.insertion_index] = x;
+            self.data[self.insertion_index] = MaybeUninit::new(x);
             self.length += 1;
         } else {
-            old = Some(replace(&mut self.data[self.insertion_index], x));
+            let prev = replace(&mut self.data[self.insertion_index], MaybeUninit::new(x));
+            old = Some(unsafe { prev.assume_init() });
         }
 
         self.insertion_index = (self.insertion_index + 1) % self.capacity();
@@ -142,7 +202,7 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
             self.insertion_index -= 1;
         }
         self.length -= 1;
-        Some(&self.data[self.insertion_index])
+        Some(unsafe { self.data[self.insertion_index].assume_init_ref() })
     }
 
     /// Returns an iterator over the queue's contents.
@@ -152,7 +212,10 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
     #[inline]
     pub fn iter(&self) -> Iter<T> {
         let (a, b) = self.data[0..self.length].split_at(self.insertion_index);
-        a.iter().rev().chain(b.iter().rev())
+        a.iter()
+            .rev()
+            .chain(b.iter().rev())
+            .map(uninit_ref as fn(&MaybeUninit<T>) -> &T)
     }
 
     /// Returns a mutable iterator over the queue's contents.
@@ -161,8 +224,11 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
     ///
     #[inline]
     pub fn iter_mut(&mut self) -> IterMut<T> {
-        let (a, b) = self.data.split_at_mut(self.insertion_index);
-        a.iter_mut().rev().chain(b.iter_mut().rev())
+        let (a, b) = self.data[0..self.length].split_at_mut(self.insertion_index);
+        a.iter_mut()
+            .rev()
+            .chain(b.iter_mut().rev())
+            .map(uninit_mut as fn(&mut MaybeUninit<T>) -> &mut T)
     }
 
     /// Returns an ascending iterator over the queue's contents.
@@ -171,8 +237,10 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
     ///
     #[inline]
     pub fn asc_iter(&self) -> AscIter<T> {
-        let (a, b) = self.data.split_at(self.insertion_index);
-        b.iter().chain(a.iter())
+        let (a, b) = self.data[0..self.length].split_at(self.insertion_index);
+        b.iter()
+            .chain(a.iter())
+            .map(uninit_ref as fn(&MaybeUninit<T>) -> &T)
     }
 
     /// Returns a mutable ascending iterator over the queue's contents.
@@ -181,8 +249,203 @@ impl<T: Default + Sized + PartialEq, N: ArrayLength> CircularQueue<T, N> {
     ///
     #[inline]
     pub fn asc_iter_mut(&mut self) -> AscIterMut<T> {
-        let (a, b) = self.data.split_at_mut(self.insertion_index);
-        b.iter_mut().chain(a.iter_mut())
+        let (a, b) = self.data[0..self.length].split_at_mut(self.insertion_index);
+        b.iter_mut()
+            .chain(a.iter_mut())
+            .map(uninit_mut as fn(&mut MaybeUninit<T>) -> &mut T)
+    }
+
+    /// Returns the queue's contents as two contiguous, oldest-to-newest slices, mirroring
+    /// `VecDeque::as_slices`.
+    ///
+    /// The first slice holds the segment running from the write cursor through the end of the
+    /// live region, the second holds the segment before it.
+    ///
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (a, b) = self.data[0..self.length].split_at(self.insertion_index);
+        // SAFETY: `a` and `b` only cover the `length` initialized slots, and `MaybeUninit<T>`
+        // is guaranteed to have the same layout as `T`.
+        unsafe {
+            let b = &*(b as *const [MaybeUninit<T>] as *const [T]);
+            let a = &*(a as *const [MaybeUninit<T>] as *const [T]);
+            (b, a)
+        }
+    }
+
+    /// Returns the queue's contents as two mutable, oldest-to-newest slices, mirroring
+    /// `VecDeque::as_mut_slices`.
+    ///
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (a, b) = self.data[0..self.length].split_at_mut(self.insertion_index);
+        // SAFETY: `a` and `b` only cover the `length` initialized slots, and `MaybeUninit<T>`
+        // is guaranteed to have the same layout as `T`.
+        unsafe {
+            let b = &mut *(b as *mut [MaybeUninit<T>] as *mut [T]);
+            let a = &mut *(a as *mut [MaybeUninit<T>] as *mut [T]);
+            (b, a)
+        }
+    }
+
+    /// Returns an iterator that removes and yields every element, oldest-to-newest, emptying the
+    /// queue.
+    ///
+    /// The queue is considered empty as soon as this is called, not just once the returned
+    /// `Drain` finishes: `length`/`insertion_index` are reset up front, and `Drain` tracks the
+    /// elements still to be yielded itself. This way, `mem::forget`-ing a partially-consumed
+    /// `Drain` can only leak the remaining elements, matching the drop-safety contract
+    /// `VecDeque::drain` upholds, rather than leaving the queue's bookkeeping pointing at slots
+    /// that no longer hold what it thinks they do.
+    ///
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, T, N> {
+        let front = self.oldest_index();
+        let remaining = self.length;
+        self.length = 0;
+        self.insertion_index = 0;
+        Drain {
+            queue: self,
+            front,
+            remaining,
+        }
+    }
+
+    /// Removes and yields only the elements matching `f`, oldest-to-newest. The remaining
+    /// elements are written back, compacted, to the start of the queue's storage when the
+    /// returned `ExtractIf` is dropped.
+    ///
+    /// As with `drain`, the queue is considered empty as soon as this is called: `length`/
+    /// `insertion_index` are reset up front, and `ExtractIf` tracks its own read cursor and the
+    /// elements still to be scanned. `mem::forget`-ing a partially-consumed `ExtractIf` can then
+    /// only leak the not-yet-returned and staged-`kept` elements, matching the drop-safety
+    /// contract `VecDeque::extract_if` upholds, rather than leaving the queue believing slots
+    /// that were already moved out are still live.
+    ///
+    #[inline]
+    pub fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, N, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let front = self.oldest_index();
+        let remaining = self.length;
+        self.length = 0;
+        self.insertion_index = 0;
+        ExtractIf {
+            remaining,
+            read: front,
+            kept: Vec::new(),
+            queue: self,
+            pred: f,
+        }
+    }
+
+    #[inline]
+    fn oldest_index(&self) -> usize {
+        let cap = self.capacity();
+        if cap == 0 {
+            0
+        } else {
+            (self.insertion_index + cap - self.length) % cap
+        }
+    }
+}
+
+impl<T, N: ArrayLength> Default for CircularQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator that drains a `CircularQueue`, oldest-to-newest, returned by `drain()`.
+///
+/// `queue.length`/`insertion_index` are already reset by the time this exists (see `drain()`), so
+/// `Drain` keeps its own count of the elements still to be yielded.
+pub struct Drain<'a, T, N: ArrayLength> {
+    queue: &'a mut CircularQueue<T, N>,
+    front: usize,
+    remaining: usize,
+}
+
+impl<T, N: ArrayLength> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.front;
+        let value = unsafe { self.queue.data[idx].assume_init_read() };
+        self.front = (idx + 1) % self.queue.capacity();
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, N: ArrayLength> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+    }
+}
+
+/// An iterator that removes and yields only the matching elements of a `CircularQueue`,
+/// oldest-to-newest, returned by `extract_if()`.
+///
+/// `queue.length`/`insertion_index` are already reset by the time this exists (see
+/// `extract_if()`), so `ExtractIf` keeps its own read cursor and remaining count. The elements
+/// that don't match are staged in `kept` and written back into the queue, starting at index zero,
+/// once the whole live region has been scanned. Compacting in place instead would risk the read
+/// cursor clobbering not-yet-visited slots whenever the queue has wrapped.
+pub struct ExtractIf<'a, T, N: ArrayLength, F>
+where
+    F: FnMut(&T) -> bool,
+{
+    queue: &'a mut CircularQueue<T, N>,
+    pred: F,
+    read: usize,
+    remaining: usize,
+    kept: Vec<T>,
+}
+
+impl<T, N: ArrayLength, F> Iterator for ExtractIf<'_, T, N, F>
+where
+    F: FnMut(&T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let cap = self.queue.capacity();
+        while self.remaining > 0 {
+            let idx = self.read;
+            self.read = (idx + 1) % cap;
+            self.remaining -= 1;
+
+            let value = unsafe { self.queue.data[idx].assume_init_read() };
+            if (self.pred)(&value) {
+                return Some(value);
+            }
+            self.kept.push(value);
+        }
+        None
+    }
+}
+
+impl<T, N: ArrayLength, F> Drop for ExtractIf<'_, T, N, F>
+where
+    F: FnMut(&T) -> bool,
+{
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+        let kept_len = self.kept.len();
+        for (i, value) in self.kept.drain(..).enumerate() {
+            self.queue.data[i] = MaybeUninit::new(value);
+        }
+        self.queue.length = kept_len;
+        self.queue.insertion_index = kept_len % self.queue.capacity().max(1);
     }
 }
 
@@ -455,4 +718,196 @@ mod tests {
         q2.push(());
         assert_eq!(q1, q2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn not_default_constructible() {
+        struct NoDefault(i32);
+
+        let mut q = CircularQueue::<_, U3>::new();
+        q.push(NoDefault(1));
+        q.push(NoDefault(2));
+
+        assert_eq!(q.len(), 2);
+        assert_eq!(q.iter().next().unwrap().0, 2);
+    }
+
+    #[test]
+    fn as_slices_partial() {
+        let mut q = CircularQueue::<_, U5>::new();
+        q.push(1);
+        q.push(2);
+        q.push(3);
+
+        let (a, b) = q.as_slices();
+        assert_eq!(a, []);
+        assert_eq!(b, [1, 2, 3]);
+    }
+
+    #[test]
+    fn as_slices_wrapped() {
+        let mut q = CircularQueue::<_, U5>::new();
+        q.push(1);
+        q.push(2);
+        q.push(3);
+        q.push(4);
+        q.push(5);
+        q.push(6);
+        q.push(7);
+
+        let (a, b) = q.as_slices();
+        let combined: Vec<_> = a.iter().chain(b.iter()).copied().collect();
+        assert_eq!(combined, [3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn drain_empties_the_queue_oldest_to_newest() {
+        let mut q = CircularQueue::<_, U5>::new();
+        q.push(1);
+        q.push(2);
+        q.push(3);
+        q.push(4);
+        q.push(5);
+        q.push(6);
+        q.push(7);
+
+        let drained: Vec<_> = q.drain().collect();
+        assert_eq!(drained, [3, 4, 5, 6, 7]);
+        assert!(q.is_empty());
+
+        q.push(1);
+        assert_eq!(q.len(), 1);
+    }
+
+    #[test]
+    fn drain_partially_consumed_still_empties() {
+        let mut q = CircularQueue::<_, U5>::new();
+        q.push(1);
+        q.push(2);
+        q.push(3);
+
+        {
+            let mut drain = q.drain();
+            assert_eq!(drain.next(), Some(1));
+        }
+
+        assert!(q.is_empty());
+        q.push(9);
+        let res: Vec<_> = q.iter().map(|&x| x).collect();
+        assert_eq!(res, [9]);
+    }
+
+    #[test]
+    fn extract_if_removes_only_matching_elements() {
+        let mut q = CircularQueue::<_, U5>::new();
+        q.push(1);
+        q.push(2);
+        q.push(3);
+        q.push(4);
+        q.push(5);
+        q.push(6);
+        q.push(7);
+
+        let removed: Vec<_> = q.extract_if(|&x| x % 2 == 0).collect();
+        assert_eq!(removed, [4, 6]);
+
+        let res: Vec<_> = q.asc_iter().map(|&x| x).collect();
+        assert_eq!(res, [3, 5, 7]);
+        assert_eq!(q.len(), 3);
+
+        q.push(8);
+        let res: Vec<_> = q.asc_iter().map(|&x| x).collect();
+        assert_eq!(res, [3, 5, 7, 8]);
+    }
+
+    #[test]
+    fn extract_if_dropped_early_still_compacts() {
+        let mut q = CircularQueue::<_, U5>::new();
+        q.push(1);
+        q.push(2);
+        q.push(3);
+        q.push(4);
+
+        {
+            let mut extracted = q.extract_if(|&x| x % 2 == 0);
+            assert_eq!(extracted.next(), Some(2));
+        }
+
+        // Dropping the iterator early still scans (and removes matches from) the rest of the
+        // queue, matching `VecDeque::extract_if`'s drop-safety contract.
+        let res: Vec<_> = q.asc_iter().map(|&x| x).collect();
+        assert_eq!(res, [1, 3]);
+    }
+
+    #[test]
+    fn drain_forgotten_midway_only_leaks_the_rest() {
+        use std::sync::atomic::{AtomicUsize as StdAtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct CountedDrop(Arc<StdAtomicUsize>);
+
+        impl Drop for CountedDrop {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let dropped = Arc::new(StdAtomicUsize::new(0));
+        let mut q = CircularQueue::<_, U5>::new();
+        for _ in 0..5 {
+            q.push(CountedDrop(dropped.clone()));
+        }
+
+        let mut drain = q.drain();
+        drop(drain.next().unwrap());
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+
+        // `mem::forget`-ing a partially-consumed `Drain` must not leave `q` with stale
+        // bookkeeping: the queue already looks empty (set up front by `drain()`), so this is
+        // safe to use immediately and only leaks the 4 elements `drain` never got to yield.
+        std::mem::forget(drain);
+
+        assert!(q.is_empty());
+        assert_eq!(q.len(), 0);
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+
+        q.push(CountedDrop(dropped.clone()));
+        assert_eq!(q.len(), 1);
+    }
+
+    #[test]
+    fn extract_if_forgotten_midway_only_leaks_the_rest() {
+        use std::sync::atomic::{AtomicUsize as StdAtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct CountedDrop(i32, Arc<StdAtomicUsize>);
+
+        impl Drop for CountedDrop {
+            fn drop(&mut self) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let dropped = Arc::new(StdAtomicUsize::new(0));
+        let mut q = CircularQueue::<_, U5>::new();
+        for i in 0..5 {
+            q.push(CountedDrop(i, dropped.clone()));
+        }
+
+        let mut extracted = q.extract_if(|v| v.0 % 2 == 0);
+        let first = extracted.next().unwrap();
+        assert_eq!(first.0, 0);
+        drop(first);
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+
+        // Forgetting here must not leave `q` believing slots that were already moved out (either
+        // returned above or staged into `kept`) are still live: those elements are leaked, never
+        // re-read or double-dropped, and `q` is immediately safe to use again.
+        std::mem::forget(extracted);
+
+        assert!(q.is_empty());
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+
+        q.push(CountedDrop(99, dropped.clone()));
+        assert_eq!(q.len(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! A lock-free bounded multi-producer/multi-consumer queue.
+//!
+//! `ArrayQueue` implements Dmitry Vyukov's bounded MPMC queue: every slot carries its own stamp
+//! so producers and consumers can make progress independently of one another without a single
+//! global lock. This complements the single-threaded `CircularQueue` for Copper graphs where
+//! several tasks fan in to one sink.
+//!
+
+extern crate alloc;
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use generic_array::{ArrayLength, GenericArray};
+
+use crate::cache_padded::CachePadded;
+
+struct Slot<T> {
+    stamp: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// A bounded lock-free MPMC queue backed by a `GenericArray` of stamped slots.
+pub struct ArrayQueue<T, N: ArrayLength> {
+    slots: GenericArray<Slot<T>, N>,
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+}
+
+unsafe impl<T: Send, N: ArrayLength> Sync for ArrayQueue<T, N> {}
+unsafe impl<T: Send, N: ArrayLength> Send for ArrayQueue<T, N> {}
+
+impl<T, N: ArrayLength> ArrayQueue<T, N> {
+    pub fn new() -> Self {
+        let slots = GenericArray::try_from_iter((0..N::to_usize()).map(|i| Slot {
+            stamp: AtomicUsize::new(i),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }))
+        .expect("GenericArray length mismatch");
+
+        ArrayQueue {
+            slots,
+            head: CachePadded::new(AtomicUsize::new(0)),
+            tail: CachePadded::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        N::to_usize()
+    }
+
+    /// Pushes a value into the queue.
+    ///
+    /// Returns the value back if the queue is full (including a zero-capacity queue, which is
+    /// always full).
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let cap = self.capacity();
+        if cap == 0 {
+            return Err(value);
+        }
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[tail % cap];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == tail {
+                match self
+                    .tail
+                    .compare_exchange_weak(tail, tail + 1, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => {
+                        unsafe {
+                            (*slot.value.get()).write(value);
+                        }
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => tail = current,
+                }
+            } else if stamp < tail {
+                return Err(value);
+            } else {
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Pops a value from the queue.
+    ///
+    /// Returns `None` if the queue is empty (including a zero-capacity queue, which is always
+    /// empty).
+    pub fn pop(&self) -> Option<T> {
+        let cap = self.capacity();
+        if cap == 0 {
+            return None;
+        }
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[head % cap];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == head + 1 {
+                match self
+                    .head
+                    .compare_exchange_weak(head, head + 1, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => {
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.stamp.store(head + cap, Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => head = current,
+                }
+            } else if stamp < head + 1 {
+                return None;
+            } else {
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl<T, N: ArrayLength> Default for ArrayQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N: ArrayLength> Drop for ArrayQueue<T, N> {
+    fn drop(&mut self) {
+        let cap = self.capacity();
+        let mut head = *self.head.0.get_mut();
+        let tail = *self.tail.0.get_mut();
+
+        while head != tail {
+            let slot = &mut self.slots[head % cap];
+            unsafe {
+                (*slot.value.get()).assume_init_drop();
+            }
+            head += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generic_array::typenum::consts::U0;
+    use generic_array::typenum::U4;
+    use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+    use std::sync::Arc;
+
+    #[test]
+    fn zero_capacity() {
+        let q = ArrayQueue::<i32, U0>::new();
+        assert_eq!(q.capacity(), 0);
+
+        assert_eq!(q.push(1), Err(1));
+        assert_eq!(q.pop(), None);
+    }
+
+    #[test]
+    fn push_pop_respects_fifo_order() {
+        let q = ArrayQueue::<i32, U4>::new();
+        assert!(q.push(1).is_ok());
+        assert!(q.push(2).is_ok());
+
+        assert_eq!(q.pop(), Some(1));
+        assert_eq!(q.pop(), Some(2));
+        assert_eq!(q.pop(), None);
+    }
+
+    #[test]
+    fn full_queue_rejects_push() {
+        let q = ArrayQueue::<i32, U4>::new();
+        for i in 0..4 {
+            assert!(q.push(i).is_ok());
+        }
+        assert_eq!(q.push(4), Err(4));
+    }
+
+    #[test]
+    fn wraps_around_the_backing_storage() {
+        let q = ArrayQueue::<i32, U4>::new();
+        for round in 0..3 {
+            assert!(q.push(round).is_ok());
+            assert!(q.push(round + 1).is_ok());
+            assert_eq!(q.pop(), Some(round));
+            assert_eq!(q.pop(), Some(round + 1));
+        }
+    }
+
+    #[test]
+    fn drop_runs_destructors_for_unpopped_values() {
+        struct CountedDrop(Arc<StdAtomicUsize>);
+
+        impl Drop for CountedDrop {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let dropped = Arc::new(StdAtomicUsize::new(0));
+        let q = ArrayQueue::<_, U4>::new();
+        q.push(CountedDrop(dropped.clone())).ok().unwrap();
+        q.push(CountedDrop(dropped.clone())).ok().unwrap();
+        q.pop();
+
+        drop(q);
+
+        assert_eq!(dropped.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn concurrent_producers_and_consumers_see_every_value() {
+        const TOTAL: usize = 1000;
+        let q = Arc::new(ArrayQueue::<usize, U4>::new());
+
+        let producers: Vec<_> = (0..2)
+            .map(|p| {
+                let q = q.clone();
+                std::thread::spawn(move || {
+                    for i in 0..(TOTAL / 2) {
+                        let value = p * (TOTAL / 2) + i;
+                        while q.push(value).is_err() {}
+                    }
+                })
+            })
+            .collect();
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::with_capacity(TOTAL)));
+        let consumers: Vec<_> = (0..2)
+            .map(|_| {
+                let q = q.clone();
+                let received = received.clone();
+                std::thread::spawn(move || loop {
+                    if let Some(v) = q.pop() {
+                        received.lock().unwrap().push(v);
+                    } else if received.lock().unwrap().len() >= TOTAL {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        for p in producers {
+            p.join().unwrap();
+        }
+        for c in consumers {
+            c.join().unwrap();
+        }
+
+        let mut received = received.lock().unwrap().clone();
+        received.sort_unstable();
+        assert_eq!(received, (0..TOTAL).collect::<Vec<_>>());
+    }
+}
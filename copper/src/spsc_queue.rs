@@ -0,0 +1,229 @@
+//! A wait-free single-producer/single-consumer circular queue.
+//!
+//! Unlike `CircularQueue`, which is meant to be driven from a single thread, `SpscCircularQueue`
+//! is designed to be shared between exactly one producer thread and one consumer thread without
+//! any locking. It never allocates after construction, making it suitable for handing messages
+//! between Copper tasks on the hot path.
+//!
+
+extern crate alloc;
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use generic_array::{ArrayLength, GenericArray};
+
+use crate::cache_padded::CachePadded;
+
+/// A wait-free, fixed-capacity SPSC queue backed by a `GenericArray`.
+///
+/// The queue sacrifices one slot of storage (actual usable capacity is `N - 1`) so that a full
+/// queue can be distinguished from an empty one without a separate flag: the queue is full when
+/// `(tail + 1) % N == head`.
+pub struct SpscCircularQueue<T, N: ArrayLength> {
+    data: UnsafeCell<GenericArray<MaybeUninit<T>, N>>,
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+}
+
+unsafe impl<T: Send, N: ArrayLength> Sync for SpscCircularQueue<T, N> {}
+unsafe impl<T: Send, N: ArrayLength> Send for SpscCircularQueue<T, N> {}
+
+impl<T, N: ArrayLength> SpscCircularQueue<T, N> {
+    pub fn new() -> Self {
+        let data = GenericArray::try_from_iter((0..N::to_usize()).map(|_| MaybeUninit::uninit()))
+            .expect("GenericArray length mismatch");
+        SpscCircularQueue {
+            data: UnsafeCell::new(data),
+            head: CachePadded::new(AtomicUsize::new(0)),
+            tail: CachePadded::new(AtomicUsize::new(0)),
+        }
+    }
+
+    #[inline]
+    fn raw_capacity(&self) -> usize {
+        N::to_usize()
+    }
+
+    /// Returns the usable capacity of the queue (one less than the backing storage).
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.raw_capacity().saturating_sub(1)
+    }
+
+    /// Pushes a value from the producer side.
+    ///
+    /// Returns the value back if the queue is full (including a zero-capacity queue, which is
+    /// always full).
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        let cap = self.raw_capacity();
+        if cap == 0 {
+            return Err(value);
+        }
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        let next = (tail + 1) % cap;
+
+        if next == head {
+            return Err(value);
+        }
+
+        unsafe {
+            (&mut *self.data.get())[tail] = MaybeUninit::new(value);
+        }
+        self.tail.store(next, Ordering::Release);
+        Ok(())
+    }
+
+    /// Pops a value from the consumer side.
+    ///
+    /// Returns `None` if the queue is empty (including a zero-capacity queue, which is always
+    /// empty).
+    pub fn try_pop(&self) -> Option<T> {
+        let cap = self.raw_capacity();
+        if cap == 0 {
+            return None;
+        }
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        let value = unsafe {
+            let slot = &mut (&mut *self.data.get())[head];
+            std::mem::replace(slot, MaybeUninit::uninit()).assume_init()
+        };
+        self.head.store((head + 1) % cap, Ordering::Release);
+        Some(value)
+    }
+
+    /// Returns `true` if the queue currently holds no elements.
+    ///
+    /// This is a snapshot: the other side of the queue may concurrently push or pop.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire) == self.tail.load(Ordering::Acquire)
+    }
+}
+
+impl<T, N: ArrayLength> Default for SpscCircularQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N: ArrayLength> Drop for SpscCircularQueue<T, N> {
+    fn drop(&mut self) {
+        let cap = self.raw_capacity();
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        let data = self.data.get_mut();
+
+        let mut i = head;
+        while i != tail {
+            unsafe {
+                data[i].assume_init_drop();
+            }
+            i = (i + 1) % cap;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generic_array::typenum::consts::U0;
+    use generic_array::typenum::U4;
+    use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+    use std::sync::Arc;
+
+    #[test]
+    fn zero_capacity() {
+        let q = SpscCircularQueue::<i32, U0>::new();
+        assert_eq!(q.capacity(), 0);
+        assert!(q.is_empty());
+
+        assert_eq!(q.try_push(1), Err(1));
+        assert_eq!(q.try_pop(), None);
+    }
+
+    #[test]
+    fn push_pop_respects_fifo_order() {
+        let q = SpscCircularQueue::<i32, U4>::new();
+        assert_eq!(q.capacity(), 3);
+
+        assert!(q.try_push(1).is_ok());
+        assert!(q.try_push(2).is_ok());
+
+        assert_eq!(q.try_pop(), Some(1));
+        assert_eq!(q.try_pop(), Some(2));
+        assert_eq!(q.try_pop(), None);
+    }
+
+    #[test]
+    fn full_queue_rejects_push() {
+        let q = SpscCircularQueue::<i32, U4>::new();
+        assert!(q.try_push(1).is_ok());
+        assert!(q.try_push(2).is_ok());
+        assert!(q.try_push(3).is_ok());
+
+        assert_eq!(q.try_push(4), Err(4));
+    }
+
+    #[test]
+    fn wraps_around_the_backing_storage() {
+        let q = SpscCircularQueue::<i32, U4>::new();
+        for _ in 0..3 {
+            assert!(q.try_push(1).is_ok());
+            assert!(q.try_push(2).is_ok());
+            assert_eq!(q.try_pop(), Some(1));
+            assert_eq!(q.try_pop(), Some(2));
+        }
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn drop_runs_destructors_for_unpopped_values() {
+        struct CountedDrop(Arc<StdAtomicUsize>);
+
+        impl Drop for CountedDrop {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let dropped = Arc::new(StdAtomicUsize::new(0));
+        let q = SpscCircularQueue::<_, U4>::new();
+        q.try_push(CountedDrop(dropped.clone())).ok().unwrap();
+        q.try_push(CountedDrop(dropped.clone())).ok().unwrap();
+        q.try_pop();
+
+        drop(q);
+
+        assert_eq!(dropped.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn producer_and_consumer_threads_see_every_value() {
+        let q = Arc::new(SpscCircularQueue::<i32, U4>::new());
+        let producer_q = q.clone();
+
+        let producer = std::thread::spawn(move || {
+            for i in 0..1000 {
+                while producer_q.try_push(i).is_err() {}
+            }
+        });
+
+        let mut received = Vec::with_capacity(1000);
+        while received.len() < 1000 {
+            if let Some(v) = q.try_pop() {
+                received.push(v);
+            }
+        }
+
+        producer.join().unwrap();
+        assert_eq!(received, (0..1000).collect::<Vec<_>>());
+    }
+}
@@ -8,9 +8,20 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 pub static GLOBAL: CountingAllocator = CountingAllocator::new();
 
 /// A simple allocator that counts the number of bytes allocated and deallocated.
+///
+/// It also tracks the current outstanding (live) bytes, the high-water mark that value has ever
+/// reached, and the number of allocation/deallocation calls, so a scope can report its worst-case
+/// memory footprint.
 pub struct CountingAllocator {
     allocated: AtomicUsize,
     deallocated: AtomicUsize,
+    current: AtomicUsize,
+    peak: AtomicUsize,
+    alloc_count: AtomicUsize,
+    dealloc_count: AtomicUsize,
+    pool_reserved: AtomicUsize,
+    /// Number of `ScopedAllocCounter`s currently live, across all threads.
+    active_scopes: AtomicUsize,
 }
 
 impl CountingAllocator {
@@ -18,6 +29,12 @@ impl CountingAllocator {
         CountingAllocator {
             allocated: AtomicUsize::new(0),
             deallocated: AtomicUsize::new(0),
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            alloc_count: AtomicUsize::new(0),
+            dealloc_count: AtomicUsize::new(0),
+            pool_reserved: AtomicUsize::new(0),
+            active_scopes: AtomicUsize::new(0),
         }
     }
 
@@ -29,9 +46,85 @@ impl CountingAllocator {
         self.deallocated.load(Ordering::SeqCst)
     }
 
+    /// Returns the current number of outstanding (allocated minus freed) bytes.
+    pub fn get_current(&self) -> usize {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    /// Returns the high-water mark of `get_current()` observed since construction or the last
+    /// `reset_peak()`.
+    pub fn get_peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of `alloc` calls made.
+    pub fn get_alloc_count(&self) -> usize {
+        self.alloc_count.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of `dealloc` calls made.
+    pub fn get_dealloc_count(&self) -> usize {
+        self.dealloc_count.load(Ordering::SeqCst)
+    }
+
+    /// Resets the high-water mark to the current outstanding byte count.
+    pub fn reset_peak(&self) {
+        let current = self.get_current();
+        self.peak.store(current, Ordering::SeqCst);
+    }
+
+    /// Registers a newly-entered `ScopedAllocCounter`, resetting the high-water mark only if no
+    /// other scope was already live.
+    ///
+    /// `peak` is a single process-wide value, so resetting it unconditionally would clobber an
+    /// outer or concurrently-running scope's high-water mark. While any scope is live, later ones
+    /// merely observe the shared peak rather than restarting it.
+    fn enter_scope(&self) {
+        if self.active_scopes.fetch_add(1, Ordering::SeqCst) == 0 {
+            self.reset_peak();
+        }
+    }
+
+    /// Unregisters a `ScopedAllocCounter` on drop.
+    fn exit_scope(&self) {
+        self.active_scopes.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Records that `bytes` worth of memory is now backed by a pool allocator rather than the
+    /// system heap, so `get_current()`/`get_peak()` growth can be told apart from true heap
+    /// growth once the pool's backing storage has settled.
+    pub fn register_pool_bytes(&self, bytes: usize) {
+        self.pool_reserved.fetch_add(bytes, Ordering::SeqCst);
+    }
+
+    /// Returns the total bytes reserved by pool allocators via `register_pool_bytes`.
+    pub fn get_pool_reserved(&self) -> usize {
+        self.pool_reserved.load(Ordering::SeqCst)
+    }
+
     pub fn reset(&self) {
         self.allocated.store(0, Ordering::SeqCst);
         self.deallocated.store(0, Ordering::SeqCst);
+        self.current.store(0, Ordering::SeqCst);
+        self.peak.store(0, Ordering::SeqCst);
+        self.alloc_count.store(0, Ordering::SeqCst);
+        self.dealloc_count.store(0, Ordering::SeqCst);
+        self.pool_reserved.store(0, Ordering::SeqCst);
+        self.active_scopes.store(0, Ordering::SeqCst);
+    }
+
+    /// Bumps the high-water mark up to `current` if it isn't already at least that high.
+    fn update_peak(&self, current: usize) {
+        let mut peak = self.peak.load(Ordering::SeqCst);
+        while peak < current {
+            match self
+                .peak
+                .compare_exchange_weak(peak, current, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => break,
+                Err(observed) => peak = observed,
+            }
+        }
     }
 }
 
@@ -40,6 +133,9 @@ unsafe impl GlobalAlloc for CountingAllocator {
         let ptr = System.alloc(layout);
         if !ptr.is_null() {
             self.allocated.fetch_add(layout.size(), Ordering::SeqCst);
+            self.alloc_count.fetch_add(1, Ordering::SeqCst);
+            let current = self.current.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            self.update_peak(current);
         }
         ptr
     }
@@ -47,34 +143,195 @@ unsafe impl GlobalAlloc for CountingAllocator {
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         System.dealloc(ptr, layout);
         self.deallocated.fetch_add(layout.size(), Ordering::SeqCst);
+        self.dealloc_count.fetch_add(1, Ordering::SeqCst);
+        self.current.fetch_sub(layout.size(), Ordering::SeqCst);
     }
 }
 
 /// A simple struct that counts the number of bytes allocated and deallocated in a scope.
+///
+/// `peak` is tracked on the shared `GLOBAL` allocator, so it is only meaningful in isolation when
+/// a single `ScopedAllocCounter` is live at a time. Nesting or overlapping scopes (including
+/// across threads) is safe but shares one high-water mark: the outermost scope's `reset_peak()`
+/// wins and inner/concurrent scopes observe the same running peak rather than their own.
 pub struct ScopedAllocCounter {
     bf_allocated: usize,
     bf_deallocated: usize,
+    bf_alloc_count: usize,
 }
 
 impl ScopedAllocCounter {
     pub fn new() -> Self {
+        GLOBAL.enter_scope();
         ScopedAllocCounter {
             bf_allocated: GLOBAL.get_allocated(),
             bf_deallocated: GLOBAL.get_deallocated(),
+            bf_alloc_count: GLOBAL.get_alloc_count(),
         }
     }
 }
 
+impl Default for ScopedAllocCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Build a difference between the number of bytes allocated and deallocated in the scope at drop time.
 impl Drop for ScopedAllocCounter {
     fn drop(&mut self) {
         let _allocated = GLOBAL.get_allocated() - self.bf_allocated;
         let _deallocated = GLOBAL.get_deallocated() - self.bf_deallocated;
+        let _alloc_count = GLOBAL.get_alloc_count() - self.bf_alloc_count;
+        let _peak = GLOBAL.get_peak();
+        GLOBAL.exit_scope();
         // TODO(gbin): Fix this when the logger is ready.
         // debug!(
-        //     "Allocations: +{}B -{}B",
+        //     "Allocations: +{}B -{}B, peaked at {}B across {} allocations",
         //     allocated = allocated,
         //     deallocated = deallocated,
+        //     peak = peak,
+        //     alloc_count = alloc_count,
         // );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `CountingAllocator` exercised directly, not as the installed `#[global_allocator]`: its
+    /// `alloc`/`dealloc`/scope bookkeeping is plain state, so a fresh instance is enough to test it
+    /// in isolation without disturbing `GLOBAL`.
+    fn layout(bytes: usize) -> Layout {
+        Layout::from_size_align(bytes, 8).unwrap()
+    }
+
+    #[test]
+    fn fresh_allocator_starts_at_zero() {
+        let allocator = CountingAllocator::new();
+        assert_eq!(allocator.get_allocated(), 0);
+        assert_eq!(allocator.get_deallocated(), 0);
+        assert_eq!(allocator.get_current(), 0);
+        assert_eq!(allocator.get_peak(), 0);
+        assert_eq!(allocator.get_alloc_count(), 0);
+        assert_eq!(allocator.get_dealloc_count(), 0);
+    }
+
+    #[test]
+    fn alloc_and_dealloc_update_counters_and_peak() {
+        let allocator = CountingAllocator::new();
+        let layout = layout(64);
+
+        let a = unsafe { allocator.alloc(layout) };
+        let b = unsafe { allocator.alloc(layout) };
+        assert_eq!(allocator.get_allocated(), 128);
+        assert_eq!(allocator.get_current(), 128);
+        assert_eq!(allocator.get_peak(), 128);
+        assert_eq!(allocator.get_alloc_count(), 2);
+
+        unsafe { allocator.dealloc(a, layout) };
+        assert_eq!(allocator.get_deallocated(), 64);
+        assert_eq!(allocator.get_current(), 64);
+        assert_eq!(allocator.get_dealloc_count(), 1);
+        // The high-water mark doesn't drop just because current usage did.
+        assert_eq!(allocator.get_peak(), 128);
+
+        unsafe { allocator.dealloc(b, layout) };
+        assert_eq!(allocator.get_current(), 0);
+    }
+
+    #[test]
+    fn reset_peak_rebases_to_current_usage() {
+        let allocator = CountingAllocator::new();
+        let layout = layout(32);
+
+        let a = unsafe { allocator.alloc(layout) };
+        assert_eq!(allocator.get_peak(), 32);
+
+        allocator.reset_peak();
+        let b = unsafe { allocator.alloc(layout) };
+        assert_eq!(allocator.get_peak(), 64);
+
+        unsafe {
+            allocator.dealloc(a, layout);
+            allocator.dealloc(b, layout);
+        }
+    }
+
+    #[test]
+    fn nested_scopes_only_let_the_outermost_reset_peak() {
+        let allocator = CountingAllocator::new();
+        let layout = layout(16);
+
+        let a = unsafe { allocator.alloc(layout) };
+        allocator.reset_peak();
+        assert_eq!(allocator.get_peak(), 16);
+
+        // Entering an outer scope while usage is already at 16 shouldn't move the peak.
+        allocator.enter_scope();
+        assert_eq!(allocator.get_peak(), 16);
+
+        let b = unsafe { allocator.alloc(layout) };
+        assert_eq!(allocator.get_peak(), 32);
+
+        // Lift the peak above current usage (32) so that a reset would be visible.
+        let t = unsafe { allocator.alloc(layout) };
+        unsafe { allocator.dealloc(t, layout) };
+
+        // A nested scope must not reset the peak out from under the outer one.
+        allocator.enter_scope();
+        assert_eq!(allocator.get_peak(), 48);
+
+        let c = unsafe { allocator.alloc(layout) };
+        assert_eq!(allocator.get_peak(), 48);
+
+        allocator.exit_scope();
+        // Still one scope live: the shared peak is untouched by exiting the inner one.
+        assert_eq!(allocator.get_peak(), 48);
+
+        allocator.exit_scope();
+
+        unsafe {
+            allocator.dealloc(a, layout);
+            allocator.dealloc(b, layout);
+            allocator.dealloc(c, layout);
+        }
+    }
+
+    #[test]
+    fn reset_zeroes_every_counter_including_active_scopes() {
+        let allocator = CountingAllocator::new();
+        let layout = layout(8);
+        let a = unsafe { allocator.alloc(layout) };
+        allocator.enter_scope();
+
+        allocator.reset();
+
+        assert_eq!(allocator.get_allocated(), 0);
+        assert_eq!(allocator.get_deallocated(), 0);
+        assert_eq!(allocator.get_current(), 0);
+        assert_eq!(allocator.get_peak(), 0);
+        assert_eq!(allocator.get_alloc_count(), 0);
+        assert_eq!(allocator.get_dealloc_count(), 0);
+        assert_eq!(allocator.get_pool_reserved(), 0);
+
+        // `reset()` zeroed `active_scopes` too, so the next `enter_scope()` is treated as
+        // outermost and resets the peak again. Lift the peak (to 8) above current usage (0) first
+        // so that the reset is visible.
+        let b = unsafe { allocator.alloc(layout) };
+        unsafe { allocator.dealloc(b, layout) };
+        allocator.enter_scope();
+        assert_eq!(allocator.get_peak(), 0);
+
+        unsafe { allocator.dealloc(a, layout) };
+    }
+
+    #[test]
+    fn register_pool_bytes_accumulates() {
+        let allocator = CountingAllocator::new();
+        allocator.register_pool_bytes(100);
+        allocator.register_pool_bytes(50);
+        assert_eq!(allocator.get_pool_reserved(), 150);
+    }
+}